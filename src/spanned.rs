@@ -70,7 +70,9 @@
 //! In this technique, using the `Type`'s span for the error message makes the
 //! error appear in the correct place underlining the right type.
 
-use proc_macro2::{Span, TokenStream};
+use std::ops::Range;
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
 use quote::ToTokens;
 
 /// A trait that can provide the `Span` of the complete contents of a syntax
@@ -94,6 +96,43 @@ pub trait Spanned: private::Sealed {
     ///
     /// [`Span::call_site()`]: https://docs.rs/proc-macro2/0.4/proc_macro2/struct.Span.html#method.call_site
     fn span(&self) -> Span;
+
+    /// Returns a copy of this node's tokens with every span rewritten to
+    /// `span`, as though the whole node had originated from that one
+    /// location.
+    ///
+    /// This is the inverse of [`span`]: rather than reading out a `Span`
+    /// covering the node, it produces a fresh `TokenStream` in which every
+    /// token carries the given `Span`.
+    ///
+    /// [`span`]: #tymethod.span
+    fn respanned(&self, span: Span) -> TokenStream;
+
+    /// Returns the spans of the first and last non-empty tokens of this
+    /// syntax tree node, or `Span::call_site()` for both if the node is
+    /// empty.
+    ///
+    /// Unlike [`span`], which joins all tokens into a single `Span` and on
+    /// stable Rust falls back to just the first token, this works today on
+    /// stable: callers that need to underline an entire multi-token node can
+    /// use the first span as the start of the diagnostic and the second as
+    /// the end, without depending on `procmacro2_semver_exempt`.
+    ///
+    /// [`span`]: #tymethod.span
+    fn span_range(&self) -> (Span, Span);
+
+    /// Returns the byte offsets of the complete contents of this syntax tree
+    /// node within its originating source file, or `None` if no token
+    /// carries a parseable range or the tokens come from more than one
+    /// source.
+    ///
+    /// This is computed by parsing the `Debug` representation of each
+    /// token's `Span`, which on stable Rust looks like `#0 bytes(12..34)`,
+    /// into a source id and numeric range. It gives macro authors a plain
+    /// `usize` range covering the whole node, usable for custom diagnostics
+    /// or source slicing, without needing the opaque joined `Span` that
+    /// `procmacro2_semver_exempt` provides.
+    fn byte_range(&self) -> Option<Range<usize>>;
 }
 
 mod private {
@@ -109,13 +148,103 @@ where
     fn span(&self) -> Span {
         join_spans(self.into_token_stream())
     }
+
+    fn respanned(&self, span: Span) -> TokenStream {
+        respan(self, span)
+    }
+
+    fn span_range(&self) -> (Span, Span) {
+        let mut spans = non_empty_spans(self.into_token_stream());
+        let start = match spans.next() {
+            Some(span) => span,
+            None => return (Span::call_site(), Span::call_site()),
+        };
+        let end = spans.last().unwrap_or(start);
+        (start, end)
+    }
+
+    fn byte_range(&self) -> Option<Range<usize>> {
+        let mut range: Option<(Option<u64>, usize, usize)> = None;
+        for span in non_empty_spans(self.into_token_stream()) {
+            let debug = format!("{:?}", span);
+            let (id, start, end) = match parse_byte_range(&debug) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+            range = Some(match range {
+                None => (id, start, end),
+                Some((prev_id, prev_start, prev_end)) => {
+                    if let (Some(prev_id), Some(id)) = (prev_id, id) {
+                        if prev_id != id {
+                            return None;
+                        }
+                    }
+                    (prev_id.or(id), prev_start.min(start), prev_end.max(end))
+                }
+            });
+        }
+        range.map(|(_, start, end)| start..end)
+    }
 }
 
-fn join_spans(tokens: TokenStream) -> Span {
-    let mut iter = tokens.into_iter().filter_map(|tt| {
-        // FIXME: This shouldn't be required, since optimally spans should
-        // never be invalid. This filter_map can probably be removed when
-        // https://github.com/rust-lang/rust/issues/43081 is resolved.
+// Parses the `Debug` representation of a `Span`, which on stable Rust looks
+// like `#0 bytes(12..34)` (or just `bytes(12..34)` on backends that don't tag
+// a source id), into the source id and numeric byte range it encodes.
+fn parse_byte_range(debug: &str) -> Option<(Option<u64>, usize, usize)> {
+    let bytes_start = debug.find("bytes(")?;
+    let rest = &debug[bytes_start + "bytes(".len()..];
+    let dots = rest.find("..")?;
+    let start: usize = rest[..dots].parse().ok()?;
+    let rest = &rest[dots + 2..];
+    let close = rest.find(')')?;
+    let end: usize = rest[..close].parse().ok()?;
+
+    let prefix = debug[..bytes_start].trim_end();
+    let id = if prefix.starts_with('#') {
+        prefix[1..].parse().ok()
+    } else {
+        None
+    };
+
+    Some((id, start, end))
+}
+
+/// Rewrites every token of `node` to carry `span`, producing a fresh
+/// `TokenStream` as if the node had been written at that one location.
+///
+/// This is the same rewrite that `quote_spanned!` applies to each token of an
+/// interpolated fragment, made available as a standalone operation so macro
+/// authors can force all tokens of an already-built node to point at one
+/// diagnostic location without reconstructing the node by hand.
+pub fn respan<T: ToTokens>(node: &T, span: Span) -> TokenStream {
+    respan_token_stream(node.into_token_stream(), span)
+}
+
+fn respan_token_stream(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| respan_token_tree(token, span))
+        .collect()
+}
+
+fn respan_token_tree(token: TokenTree, span: Span) -> TokenTree {
+    let mut token = token;
+    if let TokenTree::Group(group) = &token {
+        let stream = respan_token_stream(group.stream(), span);
+        let mut respanned = Group::new(group.delimiter(), stream);
+        respanned.set_span(span);
+        token = TokenTree::Group(respanned);
+    } else {
+        token.set_span(span);
+    }
+    token
+}
+
+// FIXME: This filter shouldn't be required, since optimally spans should
+// never be invalid. It can probably be removed when
+// https://github.com/rust-lang/rust/issues/43081 is resolved.
+fn non_empty_spans(tokens: TokenStream) -> impl Iterator<Item = Span> {
+    tokens.into_iter().filter_map(|tt| {
         let span = tt.span();
         let debug = format!("{:?}", span);
         if debug.ends_with("bytes(0..0)") {
@@ -123,7 +252,11 @@ fn join_spans(tokens: TokenStream) -> Span {
         } else {
             Some(span)
         }
-    });
+    })
+}
+
+fn join_spans(tokens: TokenStream) -> Span {
+    let mut iter = non_empty_spans(tokens);
 
     let mut joined = match iter.next() {
         Some(span) => span,